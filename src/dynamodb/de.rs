@@ -0,0 +1,687 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use super::error::Error;
+
+/// A `serde::Deserializer` that reads directly from an `&AttributeValue`,
+/// without going through an intermediate `serde_json::Value`.
+pub struct Deserializer<'de> {
+    input: &'de AttributeValue,
+    depth: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn from_attribute_value(input: &'de AttributeValue) -> Self {
+        Deserializer { input, depth: 0 }
+    }
+
+    /// Builds a `Deserializer` for a value nested one level below its
+    /// parent, rejecting input that recurses past
+    /// [`super::DEFAULT_RECURSION_LIMIT`] instead of blowing the stack; see
+    /// that constant's docs for why `unmarshall_t` is held to the same limit
+    /// as `unmarshall`.
+    fn nested(input: &'de AttributeValue, depth: usize) -> Result<Self, Error> {
+        if depth > super::DEFAULT_RECURSION_LIMIT {
+            return Err(Error::DepthExceeded {
+                limit: super::DEFAULT_RECURSION_LIMIT,
+            });
+        }
+        Ok(Deserializer { input, depth })
+    }
+}
+
+/// Deserialize `T` directly from an `&AttributeValue`.
+pub fn from_attribute_value<T>(input: &AttributeValue) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer::from_attribute_value(input))
+}
+
+fn parse_float(n: &str) -> Result<f64, Error> {
+    n.parse::<f64>()
+        .map_err(|_| Error::InvalidNumber(n.to_owned()))
+}
+
+/// The error for an `N` without a `.` that `deserialize_any` could not read
+/// as `i64`/`u64`: an integer literal is out of bounds, anything else is
+/// malformed.
+fn unparsed_number(n: &str) -> Error {
+    let digits = n.strip_prefix('-').unwrap_or(n);
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        Error::NumberOutOfBounds(n.to_owned())
+    } else {
+        Error::InvalidNumber(n.to_owned())
+    }
+}
+
+macro_rules! deserialize_parsed_number {
+    ($($method:ident -> $visit:ident : $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.input {
+                    AttributeValue::N(n) => {
+                        let v: $ty = n
+                            .parse()
+                            .map_err(|_| Error::InvalidNumber(n.clone()))?;
+                        visitor.$visit(v)
+                    }
+                    other => Err(unexpected(stringify!($ty), other)),
+                }
+            }
+        )*
+    };
+}
+
+fn unexpected(expected: &'static str, found: &AttributeValue) -> Error {
+    let found = match found {
+        AttributeValue::S(_) => "S",
+        AttributeValue::N(_) => "N",
+        AttributeValue::Bool(_) => "Bool",
+        AttributeValue::Null(_) => "Null",
+        AttributeValue::B(_) => "B",
+        AttributeValue::M(_) => "M",
+        AttributeValue::L(_) => "L",
+        AttributeValue::Ss(_) => "Ss",
+        AttributeValue::Ns(_) => "Ns",
+        AttributeValue::Bs(_) => "Bs",
+        _ => "unknown",
+    };
+    Error::UnexpectedType { expected, found }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::Null(_) => visitor.visit_unit(),
+            AttributeValue::Bool(b) => visitor.visit_bool(*b),
+            AttributeValue::S(s) => visitor.visit_borrowed_str(s),
+            AttributeValue::N(n) => {
+                if n.contains('.') {
+                    visitor.visit_f64(parse_float(n)?)
+                } else if let Ok(v) = n.parse::<i64>() {
+                    visitor.visit_i64(v)
+                } else if let Ok(v) = n.parse::<u64>() {
+                    visitor.visit_u64(v)
+                } else {
+                    Err(unparsed_number(n))
+                }
+            }
+            AttributeValue::B(blob) => visitor.visit_borrowed_bytes(blob.as_ref()),
+            AttributeValue::L(_) => self.deserialize_seq(visitor),
+            AttributeValue::M(_) => self.deserialize_map(visitor),
+            AttributeValue::Ss(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|s| AttributeValue::S(s.clone()))))
+            }
+            AttributeValue::Ns(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|n| AttributeValue::N(n.clone()))))
+            }
+            AttributeValue::Bs(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|b| AttributeValue::B(b.clone()))))
+            }
+            other => Err(unexpected("a supported AttributeValue", other)),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::Bool(b) => visitor.visit_bool(*b),
+            other => Err(unexpected("Bool", other)),
+        }
+    }
+
+    deserialize_parsed_number! {
+        deserialize_i8 -> visit_i8: i8,
+        deserialize_i16 -> visit_i16: i16,
+        deserialize_i32 -> visit_i32: i32,
+        deserialize_i64 -> visit_i64: i64,
+        deserialize_u8 -> visit_u8: u8,
+        deserialize_u16 -> visit_u16: u16,
+        deserialize_u32 -> visit_u32: u32,
+        deserialize_u64 -> visit_u64: u64,
+        deserialize_f32 -> visit_f32: f32,
+        deserialize_f64 -> visit_f64: f64,
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::S(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => visitor.visit_char(c),
+                    _ => Err(Error::Message(format!("expected a single character, got {s:?}"))),
+                }
+            }
+            other => Err(unexpected("S", other)),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::S(s) => visitor.visit_borrowed_str(s),
+            other => Err(unexpected("S", other)),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::B(blob) => visitor.visit_borrowed_bytes(blob.as_ref()),
+            other => Err(unexpected("B", other)),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::Null(_) => visitor.visit_unit(),
+            other => Err(unexpected("Null", other)),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == super::set::RAW_NUMBER_NAME {
+            return match self.input {
+                AttributeValue::N(n) => visitor.visit_borrowed_str(n),
+                other => Err(unexpected("N", other)),
+            };
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::L(arr) => visitor.visit_seq(BorrowedSeqAccess::new(arr, self.depth)),
+            AttributeValue::Ss(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|s| AttributeValue::S(s.clone()))))
+            }
+            AttributeValue::Ns(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|n| AttributeValue::N(n.clone()))))
+            }
+            AttributeValue::Bs(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.iter().map(|b| AttributeValue::B(b.clone()))))
+            }
+            other => Err(unexpected("L", other)),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::M(map) => visitor.visit_map(BorrowedMapAccess::new(map, self.depth)),
+            other => Err(unexpected("M", other)),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            AttributeValue::S(variant) => visitor.visit_enum(UnitVariantAccess { variant }),
+            AttributeValue::M(map) if map.len() == 1 => {
+                let (variant, value) = map.iter().next().expect("len == 1");
+                visitor.visit_enum(ValueVariantAccess {
+                    variant,
+                    value,
+                    depth: self.depth,
+                })
+            }
+            other => Err(unexpected("S or single-entry M", other)),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+/// Walks an `AttributeValue::L` borrowed straight from the input tree.
+struct BorrowedSeqAccess<'de> {
+    iter: std::slice::Iter<'de, AttributeValue>,
+    depth: usize,
+}
+
+impl<'de> BorrowedSeqAccess<'de> {
+    fn new(arr: &'de [AttributeValue], depth: usize) -> Self {
+        BorrowedSeqAccess {
+            iter: arr.iter(),
+            depth,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for BorrowedSeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed
+                .deserialize(Deserializer::nested(value, self.depth + 1)?)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Walks a synthesized sequence of owned `AttributeValue`s, used to unpack
+/// `Ss`/`Ns`/`Bs` into the scalar values they represent.
+struct OwnedSeqAccess {
+    items: std::vec::IntoIter<AttributeValue>,
+}
+
+impl OwnedSeqAccess {
+    fn new(items: impl Iterator<Item = AttributeValue>) -> Self {
+        OwnedSeqAccess {
+            items: items.collect::<Vec<_>>().into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for OwnedSeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some(value) => seed.deserialize(OwnedDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A `Deserializer` that owns its `AttributeValue` rather than borrowing it,
+/// needed when the value was synthesized (e.g. unpacked from a set) rather
+/// than borrowed from the original input tree.
+struct OwnedDeserializer {
+    value: AttributeValue,
+}
+
+impl<'de> de::Deserializer<'de> for OwnedDeserializer {
+    type Error = Error;
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    /// Unlike [`Deserializer::deserialize_newtype_struct`], which only needs
+    /// to recognize [`RAW_NUMBER_NAME`](super::set::RAW_NUMBER_NAME) for a
+    /// borrowed `AttributeValue::N`, this also has to handle it here: a
+    /// `NumberSet`'s elements reach `NumberStringSeed` through this owned
+    /// deserializer, not the borrowed one, since `Ns`/`Ss`/`Bs` are unpacked
+    /// into synthesized single-value `AttributeValue`s.
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == super::set::RAW_NUMBER_NAME {
+            return match self.value {
+                AttributeValue::N(n) => visitor.visit_string(n),
+                other => Err(unexpected("N", &other)),
+            };
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unlike [`Deserializer::deserialize_any`], this can't borrow out of
+    /// `self.value` and hand the visitor a `'de`-lifetime reference: the
+    /// value is owned locally, not borrowed from the original input tree,
+    /// so every branch here visits an owned copy instead.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            AttributeValue::Null(_) => visitor.visit_unit(),
+            AttributeValue::Bool(b) => visitor.visit_bool(b),
+            AttributeValue::S(s) => visitor.visit_string(s),
+            AttributeValue::N(n) => {
+                if n.contains('.') {
+                    visitor.visit_f64(parse_float(&n)?)
+                } else if let Ok(v) = n.parse::<i64>() {
+                    visitor.visit_i64(v)
+                } else if let Ok(v) = n.parse::<u64>() {
+                    visitor.visit_u64(v)
+                } else {
+                    Err(unparsed_number(&n))
+                }
+            }
+            AttributeValue::B(blob) => visitor.visit_byte_buf(blob.into_inner()),
+            AttributeValue::L(arr) => visitor.visit_seq(OwnedSeqAccess::new(arr.into_iter())),
+            AttributeValue::M(map) => visitor.visit_map(OwnedMapAccess::new(map)),
+            AttributeValue::Ss(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.into_iter().map(AttributeValue::S)))
+            }
+            AttributeValue::Ns(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.into_iter().map(AttributeValue::N)))
+            }
+            AttributeValue::Bs(arr) => {
+                visitor.visit_seq(OwnedSeqAccess::new(arr.into_iter().map(AttributeValue::B)))
+            }
+            other => Err(unexpected("a supported AttributeValue", &other)),
+        }
+    }
+}
+
+/// Walks an `AttributeValue::M` that was synthesized rather than borrowed
+/// from the original input tree, analogous to [`OwnedSeqAccess`].
+struct OwnedMapAccess {
+    iter: std::collections::hash_map::IntoIter<String, AttributeValue>,
+    value: Option<AttributeValue>,
+}
+
+impl OwnedMapAccess {
+    fn new(map: std::collections::HashMap<String, AttributeValue>) -> Self {
+        OwnedMapAccess {
+            iter: map.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for OwnedMapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(de::value::StringDeserializer::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(OwnedDeserializer { value })
+    }
+}
+
+/// Walks an `AttributeValue::M` borrowed straight from the input tree.
+struct BorrowedMapAccess<'de> {
+    iter: std::collections::hash_map::Iter<'de, String, AttributeValue>,
+    value: Option<&'de AttributeValue>,
+    depth: usize,
+}
+
+impl<'de> BorrowedMapAccess<'de> {
+    fn new(map: &'de std::collections::HashMap<String, AttributeValue>, depth: usize) -> Self {
+        BorrowedMapAccess {
+            iter: map.iter(),
+            value: None,
+            depth,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for BorrowedMapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(de::value::StrDeserializer::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::nested(value, self.depth + 1)?)
+    }
+}
+
+/// Drives a unit enum variant represented as `AttributeValue::S(variant)`.
+struct UnitVariantAccess<'de> {
+    variant: &'de str,
+}
+
+impl<'de> de::EnumAccess<'de> for UnitVariantAccess<'de> {
+    type Error = Error;
+    type Variant = UnitOnlyVariantAccess;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(de::value::StrDeserializer::new(self.variant))?;
+        Ok((variant, UnitOnlyVariantAccess))
+    }
+}
+
+struct UnitOnlyVariantAccess;
+
+impl<'de> de::VariantAccess<'de> for UnitOnlyVariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        Err(Error::Message("expected unit variant".into()))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::Message("expected unit variant".into()))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::Message("expected unit variant".into()))
+    }
+}
+
+/// Drives a newtype/tuple/struct variant represented as
+/// `AttributeValue::M({variant: value})`.
+struct ValueVariantAccess<'de> {
+    variant: &'de str,
+    value: &'de AttributeValue,
+    depth: usize,
+}
+
+impl<'de> de::EnumAccess<'de> for ValueVariantAccess<'de> {
+    type Error = Error;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(de::value::StrDeserializer::new(self.variant))?;
+        Ok((variant, Deserializer::nested(self.value, self.depth + 1)?))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Err(Error::Message("expected a value, found unit".into()))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
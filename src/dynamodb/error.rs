@@ -0,0 +1,93 @@
+use std::fmt;
+
+use serde::{de, ser};
+
+/// Errors produced while converting between Rust values and DynamoDB
+/// [`AttributeValue`](aws_sdk_dynamodb::types::AttributeValue)s via the
+/// native [`Serializer`](super::ser::Serializer) /
+/// [`Deserializer`](super::de::Deserializer).
+#[derive(Debug)]
+pub enum Error {
+    /// A `Serialize`/`Deserialize` implementation reported a custom error.
+    Message(String),
+    /// An `AttributeValue` variant was encountered where the target shape
+    /// expected something else (e.g. a map was requested but `S` was found).
+    UnexpectedType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A map key serialized to something other than a string.
+    KeyMustBeString,
+    /// An `AttributeValue::N` could not be parsed as the requested numeric type.
+    InvalidNumber(String),
+    /// An `AttributeValue::N` held an integer that doesn't fit in `i64`,
+    /// `u64`, or `f64` without losing precision. Mirrors simd-json's
+    /// approach of surfacing this as a typed error rather than silently
+    /// downcasting the value to a lossy `f64`.
+    NumberOutOfBounds(String),
+    /// The value being converted nested deeper than
+    /// [`DEFAULT_RECURSION_LIMIT`](super::DEFAULT_RECURSION_LIMIT) levels,
+    /// guarding the native [`Serializer`](super::ser::Serializer)/
+    /// [`Deserializer`](super::de::Deserializer) against an adversarially
+    /// (or accidentally) deep `AttributeValue::L`/`M`, or an equally deep
+    /// `Serialize` impl, blowing the stack; see that constant's docs for why
+    /// `marshall_t`/`unmarshall_t` are held to the same limit as
+    /// `marshall`/`unmarshall`'s [`MarshallError::DepthExceeded`].
+    DepthExceeded { limit: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Message(msg) => f.write_str(msg),
+            Error::UnexpectedType { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            Error::KeyMustBeString => f.write_str("map keys must be strings"),
+            Error::InvalidNumber(n) => write!(f, "invalid number: {n}"),
+            Error::NumberOutOfBounds(n) => {
+                write!(f, "number {n} is out of bounds for i64, u64, and f64")
+            }
+            Error::DepthExceeded { limit } => {
+                write!(f, "recursion limit of {limit} exceeded while converting")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Errors produced by
+/// [`marshall_with_limit`](super::marshall_with_limit)/
+/// [`unmarshall_with_limit`](super::unmarshall_with_limit) while converting
+/// adversarially (or accidentally) deep `Value`/`AttributeValue` input.
+#[derive(Debug)]
+pub enum MarshallError {
+    /// `Value::Array`/`Value::Object` (or `AttributeValue::L`/`M`) nesting
+    /// went deeper than the configured limit.
+    DepthExceeded { limit: usize },
+}
+
+impl fmt::Display for MarshallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarshallError::DepthExceeded { limit } => {
+                write!(f, "recursion limit of {limit} exceeded while converting")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MarshallError {}
@@ -0,0 +1,362 @@
+//! Conversions between `serde_json::Value`/`serde::Serialize` types and
+//! DynamoDB's [`AttributeValue`](aws_sdk_dynamodb::types::AttributeValue).
+//!
+//! Object key order is not preserved across `marshall`/`unmarshall`:
+//! `AttributeValue::M` is fixed by the AWS SDK to
+//! `std::collections::HashMap`, so key order is lost the moment `marshall`
+//! produces one, no matter what map this crate collects into on the way
+//! there. Swapping in an order-preserving map (e.g. `indexmap::IndexMap`,
+//! the approach nu-json uses via `LinkedHashMap`) wouldn't change that --
+//! there's no ordered `M` variant to collect it into -- so this crate
+//! doesn't offer a `preserve_order`-style feature. Don't depend on
+//! `marshall`/`unmarshall` for deterministic output, snapshot tests, or
+//! signing/hashing of marshalled items; an ordered round trip would need an
+//! ordered `M` variant upstream in `aws-sdk-dynamodb` first.
+
+mod de;
+mod error;
+mod ser;
+mod set;
+
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+
+pub use de::{Deserializer, from_attribute_value};
+pub use error::{Error, MarshallError};
+pub use ser::{Serializer, to_attribute_value};
+pub use set::{BinarySet, NumberSet, StringSet};
+
+/// Default recursion limit for [`marshall`]/[`unmarshall`] (and their
+/// `_opts` variants), guarding against a `Value::Array`/`Value::Object` (or
+/// `AttributeValue::L`/`M`) nested deeper than DynamoDB itself allows --
+/// whether from an attacker-crafted payload or a buggy caller -- blowing the
+/// stack, the same way `serde_json`'s own recursion limit does. Use
+/// [`marshall_with_limit`]/[`unmarshall_with_limit`] to pick a different
+/// limit or handle the error instead of panicking.
+///
+/// Also used, unconfigurably, by [`marshall_t`]/[`unmarshall_t`]'s native
+/// [`Serializer`]/[`Deserializer`] -- they have no `_with_limit` variant of
+/// their own, so there's nowhere else for a caller to plug in a different
+/// bound today.
+const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+/// The conventional object keys [`marshall_opts`]/[`unmarshall_opts`] use to
+/// tag a JSON array as a DynamoDB set when [`MarshallOptions::promote_sets`]
+/// is enabled, e.g. `{"SS": ["a", "b"]}`. Chosen to mirror the attribute
+/// names DynamoDB itself uses on the wire.
+const STRING_SET_TAG: &str = "SS";
+const NUMBER_SET_TAG: &str = "NS";
+const BINARY_SET_TAG: &str = "BS";
+
+/// Options controlling how [`marshall_opts`]/[`unmarshall_opts`] handle
+/// DynamoDB sets. The plain [`marshall`]/[`unmarshall`] always flatten
+/// `Ss`/`Ns`/`Bs` into (and read them back from) an ordinary
+/// `AttributeValue::L`, which is lossy: the round trip can't tell a set from
+/// a list.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarshallOptions {
+    /// When `true`, `Ss`/`Ns`/`Bs` round-trip through a tagged single-key
+    /// JSON object (`{"SS": [...]}`, `{"NS": [...]}`, `{"BS": [...]}`)
+    /// instead of decaying into a plain array.
+    pub promote_sets: bool,
+}
+
+/// Serialize `m` directly into an `AttributeValue` via the native
+/// [`Serializer`], skipping the `serde_json::Value` intermediate
+/// representation that `marshall`/`unmarshall` use.
+pub fn marshall_t<T: Serialize>(m: &T) -> Result<AttributeValue, Error> {
+    to_attribute_value(m)
+}
+
+/// Deserialize `T` directly from an `&AttributeValue` via the native
+/// [`Deserializer`].
+pub fn unmarshall_t<T: DeserializeOwned>(m: &AttributeValue) -> Result<T, Error> {
+    from_attribute_value(m)
+}
+
+pub fn marshall(m: &Value) -> AttributeValue {
+    marshall_opts(m, MarshallOptions::default())
+}
+
+/// Like [`marshall`], but when `opts.promote_sets` is set, a JSON object
+/// tagged with [`STRING_SET_TAG`]/[`NUMBER_SET_TAG`]/[`BINARY_SET_TAG`] (the
+/// shape produced by [`unmarshall_opts`] with the same option) is promoted
+/// back to `AttributeValue::Ss`/`Ns`/`Bs` instead of an ordinary `M`/`L`.
+///
+/// Panics if `m` nests deeper than [`DEFAULT_RECURSION_LIMIT`]; use
+/// [`marshall_with_limit`] to pick a different limit or handle that case as
+/// an `Err` instead.
+pub fn marshall_opts(m: &Value, opts: MarshallOptions) -> AttributeValue {
+    marshall_at_depth(m, opts, 0, DEFAULT_RECURSION_LIMIT).expect(
+        "marshall: input nested past the default recursion limit; use marshall_with_limit to raise it or handle the error",
+    )
+}
+
+/// Like [`marshall`], but returns [`MarshallError::DepthExceeded`] instead of
+/// recursing past `limit` levels deep, guarding against an adversarially (or
+/// accidentally) deep `Value::Array`/`Value::Object` blowing the stack.
+pub fn marshall_with_limit(m: &Value, limit: usize) -> Result<AttributeValue, MarshallError> {
+    marshall_opts_with_limit(m, MarshallOptions::default(), limit)
+}
+
+/// Combines [`marshall_opts`] and [`marshall_with_limit`]: honors `opts`
+/// (e.g. `promote_sets`) while returning [`MarshallError::DepthExceeded`]
+/// instead of recursing past `limit` levels deep.
+pub fn marshall_opts_with_limit(
+    m: &Value,
+    opts: MarshallOptions,
+    limit: usize,
+) -> Result<AttributeValue, MarshallError> {
+    marshall_at_depth(m, opts, 0, limit)
+}
+
+fn marshall_at_depth(
+    m: &Value,
+    opts: MarshallOptions,
+    depth: usize,
+    limit: usize,
+) -> Result<AttributeValue, MarshallError> {
+    if depth > limit {
+        return Err(MarshallError::DepthExceeded { limit });
+    }
+
+    if opts.promote_sets {
+        if let Some(set) = tagged_set(m) {
+            return Ok(set);
+        }
+    }
+
+    Ok(match m {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(b) => AttributeValue::Bool(*b),
+        Value::Number(n) => AttributeValue::N(n.to_string()),
+        Value::String(s) => AttributeValue::S(s.to_owned()),
+        Value::Array(arr) => AttributeValue::L(
+            arr.iter()
+                .map(|v| marshall_at_depth(v, opts, depth + 1, limit))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Object(o) => {
+            let new_map = o
+                .iter()
+                .map(|(k, v)| Ok((k.to_owned(), marshall_at_depth(v, opts, depth + 1, limit)?)))
+                .collect::<Result<HashMap<String, AttributeValue>, MarshallError>>()?;
+
+            AttributeValue::M(new_map)
+        }
+    })
+}
+
+/// Recognizes the single-key tagged object [`unmarshall_opts`] emits for a
+/// set, e.g. `{"SS": ["a", "b"]}`, returning the `Ss`/`Ns`/`Bs` it encodes.
+fn tagged_set(m: &Value) -> Option<AttributeValue> {
+    let Value::Object(o) = m else {
+        return None;
+    };
+    if o.len() != 1 {
+        return None;
+    }
+
+    if let Some(Value::Array(arr)) = o.get(STRING_SET_TAG) {
+        return Some(AttributeValue::Ss(
+            arr.iter().filter_map(|v| v.as_str().map(str::to_owned)).collect(),
+        ));
+    }
+    if let Some(Value::Array(arr)) = o.get(NUMBER_SET_TAG) {
+        return Some(AttributeValue::Ns(
+            arr.iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(s.to_owned()),
+                    _ => None,
+                })
+                .collect(),
+        ));
+    }
+    if let Some(Value::Array(arr)) = o.get(BINARY_SET_TAG) {
+        return Some(AttributeValue::Bs(
+            arr.iter().filter_map(|v| match v {
+                Value::Array(bytes) => Some(Blob::new(
+                    bytes
+                        .iter()
+                        .filter_map(Value::as_u64)
+                        .filter(|b| *b <= u8::MAX as u64)
+                        .map(|b| b as u8)
+                        .collect::<Vec<u8>>(),
+                )),
+                _ => None,
+            }).collect(),
+        ));
+    }
+
+    None
+}
+
+pub fn unmarshall(m: &AttributeValue) -> Value {
+    unmarshall_opts(m, MarshallOptions::default())
+}
+
+/// Like [`unmarshall`], but when `opts.promote_sets` is set, `Ss`/`Ns`/`Bs`
+/// round-trip through a tagged single-key JSON object instead of decaying
+/// into a plain array, so [`marshall_opts`] with the same option can
+/// recover the original set.
+///
+/// Panics if `m` nests deeper than [`DEFAULT_RECURSION_LIMIT`]; use
+/// [`unmarshall_with_limit`] to pick a different limit or handle that case
+/// as an `Err` instead.
+pub fn unmarshall_opts(m: &AttributeValue, opts: MarshallOptions) -> Value {
+    unmarshall_at_depth(m, opts, 0, DEFAULT_RECURSION_LIMIT).expect(
+        "unmarshall: input nested past the default recursion limit; use unmarshall_with_limit to raise it or handle the error",
+    )
+}
+
+/// Like [`unmarshall`], but returns [`MarshallError::DepthExceeded`] instead
+/// of recursing past `limit` levels deep, guarding against an adversarially
+/// (or accidentally) deep `AttributeValue::L`/`M` blowing the stack.
+pub fn unmarshall_with_limit(m: &AttributeValue, limit: usize) -> Result<Value, MarshallError> {
+    unmarshall_opts_with_limit(m, MarshallOptions::default(), limit)
+}
+
+/// Combines [`unmarshall_opts`] and [`unmarshall_with_limit`]: honors `opts`
+/// (e.g. `promote_sets`) while returning [`MarshallError::DepthExceeded`]
+/// instead of recursing past `limit` levels deep.
+pub fn unmarshall_opts_with_limit(
+    m: &AttributeValue,
+    opts: MarshallOptions,
+    limit: usize,
+) -> Result<Value, MarshallError> {
+    unmarshall_at_depth(m, opts, 0, limit)
+}
+
+fn unmarshall_at_depth(
+    m: &AttributeValue,
+    opts: MarshallOptions,
+    depth: usize,
+    limit: usize,
+) -> Result<Value, MarshallError> {
+    if depth > limit {
+        return Err(MarshallError::DepthExceeded { limit });
+    }
+
+    Ok(match m {
+        AttributeValue::Ss(arr) if opts.promote_sets => tagged(
+            STRING_SET_TAG,
+            arr.iter().map(|s| Value::String(s.to_owned())).collect(),
+        ),
+        // Tagged as raw digit strings, not `unmarshall_number`'s inferred
+        // `Value::Number`s: an `Ns` element that's out of `i64`/`u64`/`f64`
+        // range (without `arbitrary_precision`) has no `Value::Number`
+        // representation at all, and even an in-range one would get
+        // reformatted on the way back through `serde_json` (`"19.90"` ->
+        // `"19.9"`, `"007"` -> `"7"`). Carrying the string through verbatim
+        // keeps this round trip as lossless as the native `NumberSet`/
+        // `RAW_NUMBER_NAME` path.
+        AttributeValue::Ns(arr) if opts.promote_sets => tagged(
+            NUMBER_SET_TAG,
+            arr.iter().map(|n| Value::String(n.to_owned())).collect(),
+        ),
+        AttributeValue::Bs(arr) if opts.promote_sets => {
+            tagged(BINARY_SET_TAG, arr.iter().map(unmarshall_blob).collect())
+        }
+        AttributeValue::S(s) => Value::String(s.to_owned()),
+        AttributeValue::B(blob) => unmarshall_blob(blob),
+        AttributeValue::Bool(b) => Value::Bool(*b),
+        AttributeValue::M(o) => {
+            let new_map = o
+                .iter()
+                .map(|(k, v)| Ok((k.to_owned(), unmarshall_at_depth(v, opts, depth + 1, limit)?)))
+                .collect::<Result<Map<String, Value>, MarshallError>>()?;
+            Value::Object(new_map)
+        }
+        AttributeValue::N(v) => unmarshall_number(v),
+        AttributeValue::L(arr) => Value::Array(
+            arr.iter()
+                .map(|v| unmarshall_at_depth(v, opts, depth + 1, limit))
+                .collect::<Result<Vec<Value>, _>>()?,
+        ),
+        AttributeValue::Ns(arr) => {
+            Value::Array(arr.iter().map(|n| unmarshall_number(n)).collect())
+        }
+        AttributeValue::Bs(arr) => Value::Array(arr.iter().map(unmarshall_blob).collect()),
+        AttributeValue::Ss(arr) => Value::Array(
+            arr.iter()
+                .map(|s| Value::String(s.to_owned()))
+                .collect::<Vec<Value>>(),
+        ),
+        _ => Value::Null, // covers AttributeValue::Null(_) too
+    })
+}
+
+/// Converts a DynamoDB `N`'s digit string into a JSON number, the leaf
+/// conversion shared by `AttributeValue::N` and the elements of
+/// `Ns`/`AttributeValue::Ns`. Pulled out of [`unmarshall_at_depth`] so those
+/// elements are treated as leaves (no recursive call, no depth increment) the
+/// same way `Ss`'s elements already are -- a number set isn't a level of
+/// nesting any more than a string set is.
+fn unmarshall_number(v: &str) -> Value {
+    if v.contains('.') {
+        v.parse::<f64>().map_or_else(
+            |_| serde_json::json!(v),
+            |parsed_float| serde_json::json!(parsed_float),
+        )
+    } else if let Ok(parsed_int) = v.parse::<i64>() {
+        serde_json::json!(parsed_int)
+    } else if let Ok(parsed_uint) = v.parse::<u64>() {
+        serde_json::json!(parsed_uint)
+    } else if let Some(arbitrary) = arbitrary_precision_number(v) {
+        Value::Number(arbitrary)
+    } else {
+        serde_json::json!(v)
+    }
+}
+
+/// Converts a DynamoDB `B` blob into a JSON array of byte values, the leaf
+/// conversion shared by `AttributeValue::B` and the elements of
+/// `Bs`/`AttributeValue::Bs`. See [`unmarshall_number`] for why this doesn't
+/// recurse through `unmarshall_at_depth`.
+fn unmarshall_blob(blob: &Blob) -> Value {
+    Value::Array(
+        blob.clone()
+            .into_inner()
+            .iter()
+            .map(|v| Value::Number(Number::from(*v)))
+            .collect::<Vec<Value>>(),
+    )
+}
+
+/// Wraps `items` under `tag` as the single-key JSON object
+/// [`marshall_opts`] recognizes via [`tagged_set`].
+fn tagged(tag: &str, items: Vec<Value>) -> Value {
+    let mut map = Map::with_capacity(1);
+    map.insert(tag.to_owned(), Value::Array(items));
+    Value::Object(map)
+}
+
+/// Parses an integer digit string too large for `i64`/`u64` into a
+/// `serde_json::Number` that preserves every digit, for callers that built
+/// `serde_json` with its `arbitrary_precision` feature. Without that
+/// feature, `Number` can't represent the value losslessly, so there's
+/// nothing to return and the caller falls back to `Value::String`.
+///
+/// Returns `None` (instead of handing `Number::from_string_unchecked` a
+/// string it doesn't validate) unless `v` is an optional `-` followed by
+/// one or more ASCII digits -- a malformed `N` like `"123abc"` should fall
+/// back to `Value::String` the same way it does without this feature, not
+/// become a `Number` that isn't even valid JSON on serialization.
+#[cfg(feature = "arbitrary_precision")]
+fn arbitrary_precision_number(v: &str) -> Option<Number> {
+    let digits = v.strip_prefix('-').unwrap_or(v);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(Number::from_string_unchecked(v.to_owned()))
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+fn arbitrary_precision_number(_v: &str) -> Option<Number> {
+    None
+}
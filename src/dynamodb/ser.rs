@@ -0,0 +1,985 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::Serialize;
+use serde::ser::{self, Impossible};
+
+use super::error::Error;
+
+/// A `serde::Serializer` that targets `AttributeValue` directly, without
+/// going through an intermediate `serde_json::Value`.
+///
+/// This preserves information the JSON round trip would otherwise lose, such
+/// as byte buffers serialized through `serialize_bytes`: a field such as
+/// `#[serde(with = "serde_bytes")] data: Vec<u8>` serializes straight to
+/// `AttributeValue::B`, rather than the `L` of single-byte `N`s a plain
+/// `Vec<u8>` produces (that numeric-array shape is kept as the default --
+/// and therefore the opt-out -- since `Vec<u8>`'s own `Serialize` impl goes
+/// through `serialize_seq`, not `serialize_bytes`, unless a wrapper like
+/// `serde_bytes` is used to route it through the bytes hook).
+pub struct Serializer {
+    depth: usize,
+}
+
+/// Serialize `value` directly into an `AttributeValue`.
+pub fn to_attribute_value<T>(value: &T) -> Result<AttributeValue, Error>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(Serializer { depth: 0 })
+}
+
+/// Serializes a value nested one level below its parent, rejecting input
+/// that recurses past [`super::DEFAULT_RECURSION_LIMIT`] instead of blowing
+/// the stack; see that constant's docs for why `marshall_t` is held to the
+/// same limit as `marshall`.
+fn to_attribute_value_at_depth<T>(value: &T, depth: usize) -> Result<AttributeValue, Error>
+where
+    T: ?Sized + Serialize,
+{
+    if depth > super::DEFAULT_RECURSION_LIMIT {
+        return Err(Error::DepthExceeded {
+            limit: super::DEFAULT_RECURSION_LIMIT,
+        });
+    }
+    value.serialize(Serializer { depth })
+}
+
+fn number(v: impl ToString) -> AttributeValue {
+    AttributeValue::N(v.to_string())
+}
+
+fn format_float(v: f64) -> String {
+    // Reuse `serde_json::Number`'s float formatting so that `N` strings
+    // produced here agree digit-for-digit with the legacy `Value`-based
+    // `marshall`, which goes through the very same type. `v` is always
+    // finite here -- `serialize_f64` maps NaN/Infinity to `Null` before
+    // reaching this point -- so `Number::from_f64` always succeeds.
+    serde_json::Number::from_f64(v)
+        .expect("format_float is only called with finite floats")
+        .to_string()
+}
+
+macro_rules! serialize_int {
+    ($($method:ident : $ty:ty),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<AttributeValue, Error> {
+                Ok(number(v))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Bool(v))
+    }
+
+    serialize_int! {
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<AttributeValue, Error> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<AttributeValue, Error> {
+        if !v.is_finite() {
+            // DynamoDB's `N` can't represent NaN/Infinity. Mirror the legacy
+            // `marshall(&Value)` path, which loses the same information by
+            // going through `serde_json::Value::from(f64)`, itself mapping a
+            // non-finite float to `Value::Null` rather than a `Number`.
+            return Ok(AttributeValue::Null(true));
+        }
+        Ok(AttributeValue::N(format_float(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::B(Blob::new(v.to_vec())))
+    }
+
+    fn serialize_none(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<AttributeValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<AttributeValue, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::S(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, Error> {
+        match name {
+            super::set::STRING_SET_NAME => {
+                Ok(AttributeValue::Ss(value.serialize(StringVecSerializer)?))
+            }
+            super::set::NUMBER_SET_NAME => {
+                Ok(AttributeValue::Ns(value.serialize(StringVecSerializer)?))
+            }
+            super::set::BINARY_SET_NAME => Ok(AttributeValue::Bs(
+                value
+                    .serialize(BytesVecSerializer)?
+                    .into_iter()
+                    .map(Blob::new)
+                    .collect(),
+            )),
+            _ => value.serialize(self),
+        }
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, Error> {
+        let mut map = HashMap::with_capacity(1);
+        map.insert(
+            variant.to_owned(),
+            to_attribute_value_at_depth(value, self.depth + 1)?,
+        );
+        Ok(AttributeValue::M(map))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            depth: self.depth,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, Error> {
+        Ok(SerializeTupleVariant {
+            variant,
+            items: Vec::with_capacity(len),
+            depth: self.depth,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: HashMap::new(),
+            next_key: None,
+            depth: self.depth,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, Error> {
+        Ok(SerializeMap {
+            map: HashMap::with_capacity(len),
+            next_key: None,
+            depth: self.depth,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant, Error> {
+        Ok(SerializeStructVariant {
+            variant,
+            map: HashMap::with_capacity(len),
+            depth: self.depth,
+        })
+    }
+}
+
+/// Accumulates the elements of a sequence/tuple before collapsing them into
+/// `AttributeValue::L`.
+pub struct SerializeVec {
+    items: Vec<AttributeValue>,
+    depth: usize,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items
+            .push(to_attribute_value_at_depth(value, self.depth + 1)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::L(self.items))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Accumulates the fields of a tuple variant, emitted as
+/// `{variant: [field, ...]}` to mirror serde's externally-tagged representation.
+pub struct SerializeTupleVariant {
+    variant: &'static str,
+    items: Vec<AttributeValue>,
+    depth: usize,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items
+            .push(to_attribute_value_at_depth(value, self.depth + 1)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        let mut map = HashMap::with_capacity(1);
+        map.insert(self.variant.to_owned(), AttributeValue::L(self.items));
+        Ok(AttributeValue::M(map))
+    }
+}
+
+/// Accumulates map/struct entries before collapsing them into `AttributeValue::M`.
+pub struct SerializeMap {
+    map: HashMap<String, AttributeValue>,
+    next_key: Option<String>,
+    depth: usize,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(key.serialize(MapKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error::Message("serialize_value called before serialize_key".into()))?;
+        self.map
+            .insert(key, to_attribute_value_at_depth(value, self.depth + 1)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::M(self.map))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.map.insert(
+            key.to_owned(),
+            to_attribute_value_at_depth(value, self.depth + 1)?,
+        );
+        Ok(())
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        Ok(AttributeValue::M(self.map))
+    }
+}
+
+/// Accumulates the fields of a struct variant, emitted as
+/// `{variant: {field: value, ...}}`.
+pub struct SerializeStructVariant {
+    variant: &'static str,
+    map: HashMap<String, AttributeValue>,
+    depth: usize,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.map.insert(
+            key.to_owned(),
+            to_attribute_value_at_depth(value, self.depth + 1)?,
+        );
+        Ok(())
+    }
+
+    fn end(self) -> Result<AttributeValue, Error> {
+        let mut outer = HashMap::with_capacity(1);
+        outer.insert(self.variant.to_owned(), AttributeValue::M(self.map));
+        Ok(AttributeValue::M(outer))
+    }
+}
+
+/// Serializes map keys, which DynamoDB requires to be strings.
+struct MapKeySerializer;
+
+macro_rules! key_must_be_string {
+    ($($method:ident : $ty:ty),* $(,)?) => {
+        $(
+            fn $method(self, _v: $ty) -> Result<String, Error> {
+                Err(Error::KeyMustBeString)
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for MapKeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_owned())
+    }
+
+    key_must_be_string! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::KeyMustBeString)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::KeyMustBeString)
+    }
+}
+
+/// Stubs out every `Serializer` method that a DynamoDB set element can't be,
+/// so the handful of element serializers below only need to spell out the
+/// one or two shapes they actually accept.
+macro_rules! only_scalar_elements {
+    ($ok:ty) => {
+        fn serialize_bool(self, _v: bool) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_i8(self, _v: i8) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_i16(self, _v: i16) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_i32(self, _v: i32) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_i64(self, _v: i64) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_u8(self, _v: u8) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_u16(self, _v: u16) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_u32(self, _v: u32) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_u64(self, _v: u64) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_f32(self, _v: f32) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_f64(self, _v: f64) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_char(self, _v: char) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_none(self) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<$ok, Error> {
+            value.serialize(self)
+        }
+        fn serialize_unit(self) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+        ) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<$ok, Error> {
+            value.serialize(self)
+        }
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<$ok, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleStruct, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleVariant, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStruct, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStructVariant, Error> {
+            Err(Error::Message("expected a set element".into()))
+        }
+    };
+}
+
+/// Serializes the `Vec<String>` backing a [`StringSet`](super::set::StringSet)
+/// or [`NumberSet`](super::set::NumberSet) into a flat `Vec<String>`.
+struct StringVecSerializer;
+
+impl ser::Serializer for StringVecSerializer {
+    type Ok = Vec<String>;
+    type Error = Error;
+    type SerializeSeq = StringVecCollector;
+    type SerializeTuple = Impossible<Vec<String>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<String>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<String>, Error>;
+    type SerializeMap = Impossible<Vec<String>, Error>;
+    type SerializeStruct = Impossible<Vec<String>, Error>;
+    type SerializeStructVariant = Impossible<Vec<String>, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<Vec<String>, Error> {
+        let _ = v;
+        Err(Error::Message("expected a sequence of set elements".into()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Vec<String>, Error> {
+        Err(Error::Message("expected a set element".into()))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<StringVecCollector, Error> {
+        Ok(StringVecCollector {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    only_scalar_elements!(Vec<String>);
+}
+
+struct StringVecCollector {
+    items: Vec<String>,
+}
+
+impl ser::SerializeSeq for StringVecCollector {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(StringElementSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<String>, Error> {
+        Ok(self.items)
+    }
+}
+
+/// Serializes a single set element that must be a plain string.
+struct StringElementSerializer;
+
+impl ser::Serializer for StringElementSerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(Error::Message("expected a string set element".into()))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Message("expected a string set element".into()))
+    }
+
+    only_scalar_elements!(String);
+}
+
+/// Serializes the `Vec<Vec<u8>>` backing a [`BinarySet`](super::set::BinarySet)
+/// into a flat `Vec<Vec<u8>>`.
+struct BytesVecSerializer;
+
+impl ser::Serializer for BytesVecSerializer {
+    type Ok = Vec<Vec<u8>>;
+    type Error = Error;
+    type SerializeSeq = BytesVecCollector;
+    type SerializeTuple = Impossible<Vec<Vec<u8>>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<Vec<u8>>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<Vec<u8>>, Error>;
+    type SerializeMap = Impossible<Vec<Vec<u8>>, Error>;
+    type SerializeStruct = Impossible<Vec<Vec<u8>>, Error>;
+    type SerializeStructVariant = Impossible<Vec<Vec<u8>>, Error>;
+
+    fn serialize_str(self, _v: &str) -> Result<Vec<Vec<u8>>, Error> {
+        Err(Error::Message("expected a sequence of set elements".into()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        Err(Error::Message("expected a set element".into()))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<BytesVecCollector, Error> {
+        Ok(BytesVecCollector {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    only_scalar_elements!(Vec<Vec<u8>>);
+}
+
+struct BytesVecCollector {
+    items: Vec<Vec<u8>>,
+}
+
+impl ser::SerializeSeq for BytesVecCollector {
+    type Ok = Vec<Vec<u8>>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(BytesElementSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<Vec<u8>>, Error> {
+        Ok(self.items)
+    }
+}
+
+/// Serializes a single set element that must be a byte buffer. Accepts both
+/// a true `serialize_bytes` call (e.g. `serde_bytes::ByteBuf`) and a plain
+/// `Vec<u8>`, which serializes element-by-element as a sequence of `u8`.
+struct BytesElementSerializer;
+
+impl ser::Serializer for BytesElementSerializer {
+    type Ok = Vec<u8>;
+    type Error = Error;
+    type SerializeSeq = U8VecCollector;
+    type SerializeTuple = Impossible<Vec<u8>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<u8>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<u8>, Error>;
+    type SerializeMap = Impossible<Vec<u8>, Error>;
+    type SerializeStruct = Impossible<Vec<u8>, Error>;
+    type SerializeStructVariant = Impossible<Vec<u8>, Error>;
+
+    fn serialize_str(self, _v: &str) -> Result<Vec<u8>, Error> {
+        Err(Error::Message("expected a binary set element".into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(v.to_vec())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<U8VecCollector, Error> {
+        Ok(U8VecCollector {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    only_scalar_elements!(Vec<u8>);
+}
+
+struct U8VecCollector {
+    items: Vec<u8>,
+}
+
+impl ser::SerializeSeq for U8VecCollector {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(U8ElementSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.items)
+    }
+}
+
+/// Serializes a single `u8` within a plain `Vec<u8>` set element.
+struct U8ElementSerializer;
+
+impl ser::Serializer for U8ElementSerializer {
+    type Ok = u8;
+    type Error = Error;
+    type SerializeSeq = Impossible<u8, Error>;
+    type SerializeTuple = Impossible<u8, Error>;
+    type SerializeTupleStruct = Impossible<u8, Error>;
+    type SerializeTupleVariant = Impossible<u8, Error>;
+    type SerializeMap = Impossible<u8, Error>;
+    type SerializeStruct = Impossible<u8, Error>;
+    type SerializeStructVariant = Impossible<u8, Error>;
+
+    fn serialize_u8(self, v: u8) -> Result<u8, Error> {
+        Ok(v)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_i16(self, _v: i16) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_i32(self, _v: i32) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_i64(self, _v: i64) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_u16(self, _v: u16) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_u32(self, _v: u32) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_u64(self, _v: u64) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_f32(self, _v: f32) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_f64(self, _v: f64) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_char(self, _v: char) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_none(self) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_unit(self) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<u8, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u8, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Message("expected a u8".into()))
+    }
+}
@@ -0,0 +1,288 @@
+//! Round-trippable DynamoDB String/Number/Binary sets (`SS`/`NS`/`BS`).
+//!
+//! The default marshalling collapses these into `AttributeValue::L`, which
+//! looks identical to a genuine list on the way back. Wrapping a field in
+//! [`StringSet`], [`NumberSet`], or [`BinarySet`] routes it through a
+//! reserved `serialize_newtype_struct` name that the native
+//! [`Serializer`](super::ser::Serializer) recognizes, so the attribute
+//! marshals to `Ss`/`Ns`/`Bs`; the native
+//! [`Deserializer`](super::de::Deserializer) reads those back through
+//! `deserialize_seq`, so the round trip is lossless.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+pub(crate) const STRING_SET_NAME: &str = "$__dynamodb_marshall::StringSet";
+pub(crate) const NUMBER_SET_NAME: &str = "$__dynamodb_marshall::NumberSet";
+pub(crate) const BINARY_SET_NAME: &str = "$__dynamodb_marshall::BinarySet";
+
+/// Reserved `deserialize_newtype_struct` name [`NumberStringSeed`] drives a
+/// `NumberSet` element through, so the native
+/// [`Deserializer`](super::de::Deserializer) can hand back an `N`'s digit
+/// string untouched instead of routing it through `deserialize_any`'s
+/// numeric-type inference.
+pub(crate) const RAW_NUMBER_NAME: &str = "$__dynamodb_marshall::RawNumber";
+
+/// A DynamoDB string set (`SS`). Marshals to `AttributeValue::Ss` and back,
+/// instead of decaying into an ordinary `AttributeValue::L` of `S`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringSet(pub Vec<String>);
+
+/// A DynamoDB number set (`NS`), represented as pre-formatted digit strings
+/// to avoid the precision loss of bouncing through `f64`/`i64`. Marshals to
+/// `AttributeValue::Ns` and back, instead of decaying into an ordinary
+/// `AttributeValue::L` of `N`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet(pub Vec<String>);
+
+/// A DynamoDB binary set (`BS`). Marshals to `AttributeValue::Bs` and back,
+/// instead of decaying into an ordinary `AttributeValue::L` of `L`s of
+/// single-byte `N`s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinarySet(pub Vec<Vec<u8>>);
+
+impl Serialize for StringSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(STRING_SET_NAME, &self.0)
+    }
+}
+
+impl Serialize for NumberSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(NUMBER_SET_NAME, &self.0)
+    }
+}
+
+impl Serialize for BinarySet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(BINARY_SET_NAME, &self.0)
+    }
+}
+
+/// Unwraps a reserved-name newtype struct back into the plain sequence it
+/// wraps, whatever shape the `Deserializer` used to drive that (`Ss`/`Ns`
+/// arrive via `deserialize_seq`, same as a plain `L`).
+struct SetVisitor<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> SetVisitor<T> {
+    fn new() -> Self {
+        SetVisitor {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for SetVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a DynamoDB set")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+impl<'de> Deserialize<'de> for StringSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_newtype_struct(STRING_SET_NAME, SetVisitor::<String>::new())
+            .map(StringSet)
+    }
+}
+
+impl<'de> Deserialize<'de> for NumberSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_newtype_struct(NUMBER_SET_NAME, NumberSetVisitor)
+            .map(NumberSet)
+    }
+}
+
+/// Like [`SetVisitor`], but reads each element as the raw digit string the
+/// `Ns` attribute carries instead of a generic `T: Deserialize`, since a
+/// plain `String` only deserializes from `AttributeValue::S`, not `N`.
+struct NumberSetVisitor;
+
+impl<'de> Visitor<'de> for NumberSetVisitor {
+    type Value = Vec<String>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a DynamoDB number set")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(NumberStringSeed)? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+struct NumberStringSeed;
+
+impl<'de> DeserializeSeed<'de> for NumberStringSeed {
+    type Value = String;
+
+    /// Routes through the reserved [`RAW_NUMBER_NAME`] newtype-struct name
+    /// rather than `deserialize_any`, so the native
+    /// [`Deserializer`](super::de::Deserializer)/
+    /// [`OwnedDeserializer`](super::de) can hand back the `N`'s digit string
+    /// as-is (`visit_str`) instead of `deserialize_any`'s usual numeric-type
+    /// inference, which would round-trip `"19.90"`/`"007"` through `f64`/
+    /// `i64` and reformat them as `"19.9"`/`"7"`.
+    fn deserialize<D>(self, deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(RAW_NUMBER_NAME, NumberStringVisitor)
+    }
+}
+
+struct NumberStringVisitor;
+
+impl<'de> Visitor<'de> for NumberStringVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number set element")
+    }
+
+    /// Fallback for a `Deserializer` that doesn't recognize
+    /// [`RAW_NUMBER_NAME`] (e.g. `serde_json`'s, when a `NumberSet` is
+    /// serialized to a plain JSON array of digit strings and deserialized
+    /// back from there) -- every element is already a string in that case,
+    /// so plain numeric-type inference is unnecessary.
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<String, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.to_owned())
+    }
+}
+
+impl<'de> Deserialize<'de> for BinarySet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_newtype_struct(BINARY_SET_NAME, BinarySetVisitor)
+            .map(BinarySet)
+    }
+}
+
+/// Like [`SetVisitor`], but collects byte-buffer elements via [`BytesSeed`]
+/// rather than a generic `T: Deserialize`, since a plain `Vec<u8>` would
+/// otherwise deserialize element-by-element as a sequence of `u8`.
+struct BinarySetVisitor;
+
+impl<'de> Visitor<'de> for BinarySetVisitor {
+    type Value = Vec<Vec<u8>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a DynamoDB binary set")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(BytesSeed)? {
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+struct BytesSeed;
+
+impl<'de> DeserializeSeed<'de> for BytesSeed {
+    type Value = Vec<u8>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte buffer")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Vec<u8>, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+}